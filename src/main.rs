@@ -1,15 +1,15 @@
+mod pattern;
+mod rule;
+
 use std::thread;
 use std::time::Duration;
 use std::sync::mpsc;
 use std::io::{stdin};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
 use rand::prelude::*;
 use colored::{Colorize, ColoredString};
-
-#[derive(Debug, PartialEq, Clone, Copy)]
-enum Cell {
-    Alive(u8),
-    Dead
-}
+use rule::Rule;
 
 enum Command{
     Pause,
@@ -17,13 +17,89 @@ enum Command{
     HigherRatio,
     LowerRatio,
     Faster,
-    Slower
+    Slower,
+    Load(String, Cell),
+    Save(String),
+    SetRule(String),
+    StepBack,
+    Reset,
+    ToggleTopology
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Topology {
+    Bounded,
+    Toroidal
+}
+
+impl Topology {
+    fn toggle(self) -> Topology {
+        match self {
+            Topology::Bounded => Topology::Toroidal,
+            Topology::Toroidal => Topology::Bounded
+        }
+    }
+
+    fn wrap(self, cell: Cell) -> Cell {
+        match self {
+            Topology::Bounded => cell,
+            Topology::Toroidal => (cell.0.rem_euclid(WIDTH as isize), cell.1.rem_euclid(WIDTH as isize))
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum GameError {
+    NoPreviousTurn
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::NoPreviousTurn => write!(f, "no previous turn to step back to")
+        }
+    }
 }
 
+const HISTORY_CAP: usize = 50;
+
 
 const WIDTH: usize = 20;
-type Board = [Cell; WIDTH * WIDTH];
-type Neighbours = [Cell; 8];
+type Cell = (isize, isize);
+type Board = HashMap<Cell, u8>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Nw, N, Ne,
+    W,      E,
+    Sw, S, Se
+}
+
+impl Direction {
+    const ALL: [Direction; 8] = [
+        Direction::Nw, Direction::N, Direction::Ne,
+        Direction::W,                Direction::E,
+        Direction::Sw, Direction::S, Direction::Se,
+    ];
+
+    fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Nw => (-1, -1),
+            Direction::N  => (0, -1),
+            Direction::Ne => (1, -1),
+            Direction::W  => (-1, 0),
+            Direction::E  => (1, 0),
+            Direction::Sw => (-1, 1),
+            Direction::S  => (0, 1),
+            Direction::Se => (1, 1),
+        }
+    }
+
+    fn neighbour(self, cell: Cell, topology: Topology) -> Cell {
+        let (dx, dy) = self.offset();
+        topology.wrap((cell.0 + dx, cell.1 + dy))
+    }
+}
 
 
 fn main() {
@@ -34,29 +110,84 @@ fn main() {
         let mut frame = 0;
         let mut ratio = 0.7;
         let mut board = generate_board(ratio);
+        let mut seed = board.clone();
+        let mut history: VecDeque<Board> = VecDeque::new();
         let mut sleep = 700;
+        let mut rule = Rule::default();
+        let mut topology = Topology::Bounded;
 
         loop {
             if active {
                 frame += 1;
                 print!("\x1B[2J\x1B[1;1H");
-                println!("frame: {} - ratio: {} - sleep duration(ms): {}", frame, ratio, sleep);
+                println!("frame: {} - ratio: {} - sleep duration(ms): {} - rule: {:?} - topology: {:?}", frame, ratio, sleep, rule, topology);
                 draw_board(&board);
                 println!("commands:");
-                println!("r - redraw, m - increase ratio, l - lower ratio");
+                println!("r - redraw, m - increase ratio, k - lower ratio");
                 println!("z - slower, x - faster, p - pause, q - quit");
+                println!("l <path> [x y] - load pattern at offset, w <path> - save pattern");
+                println!("b <rule> - set rule, e.g. b B3/S23");
+                println!("u - step back, i - reset to seed");
+                println!("t - toggle bounded/toroidal topology");
                 println!("you have to press enter for commands to work!");
-                board = get_updated_board(&board);
+
+                history.push_back(board.clone());
+                if history.len() > HISTORY_CAP {
+                    history.pop_front();
+                }
+                board = get_updated_board(&board, &rule, topology);
             }
 
             let msg = msg_receiver.try_recv();
             match msg {
                 Ok(Command::Pause) => { active = !active },
-                Ok(Command::Redraw) => { board = generate_board(ratio); }
+                Ok(Command::Redraw) => {
+                    board = generate_board(ratio);
+                    seed = board.clone();
+                    history.clear();
+                }
                 Ok(Command::HigherRatio) => { ratio += 0.05; }
                 Ok(Command::LowerRatio) => { ratio -= 0.05; }
                 Ok(Command::Faster) => { sleep -= 50; }
                 Ok(Command::Slower) => { sleep += 50; }
+                Ok(Command::Load(path, offset)) => {
+                    match std::fs::read_to_string(&path)
+                        .map_err(pattern::PatternError::from)
+                        .and_then(|text| pattern::parse(&text))
+                    {
+                        Ok(cells) => {
+                            board = cells.into_iter()
+                                .map(|(x, y)| ((x + offset.0, y + offset.1), 0))
+                                .collect();
+                            seed = board.clone();
+                            history.clear();
+                        }
+                        Err(e) => eprintln!("could not load pattern '{path}': {e}")
+                    }
+                }
+                Ok(Command::Save(path)) => {
+                    let cells: HashSet<Cell> = board.keys().copied().collect();
+                    if let Err(e) = std::fs::write(&path, pattern::to_rle(&cells)) {
+                        eprintln!("could not save pattern '{path}': {e}");
+                    }
+                }
+                Ok(Command::SetRule(spec)) => {
+                    match Rule::parse(&spec) {
+                        Ok(parsed) => { rule = parsed; }
+                        Err(e) => eprintln!("could not set rule '{spec}': {e}")
+                    }
+                }
+                Ok(Command::StepBack) => {
+                    match step_back(&mut history) {
+                        Ok(previous) => { board = previous; }
+                        Err(e) => eprintln!("{e}")
+                    }
+                }
+                Ok(Command::Reset) => {
+                    board = seed.clone();
+                    history.clear();
+                }
+                Ok(Command::ToggleTopology) => { topology = topology.toggle(); }
                 _ => ()
             }
             thread::sleep(Duration::from_millis(sleep));
@@ -67,15 +198,32 @@ fn main() {
         let mut input = String::new();
         match stdin().read_line(&mut input) {
             Ok(_) => {
-                match input.as_str().trim() {
-                    "q" => break,
-                    "p" => { let _ = msg_sender.send(Command::Pause); },
-                    "r" => { let _ = msg_sender.send(Command::Redraw);},
-                    "m" => { let _ = msg_sender.send(Command::HigherRatio); },
-                    "l" => { let _ = msg_sender.send(Command::LowerRatio); },
-                    "z" => { let _ = msg_sender.send(Command::Slower); },
-                    "x" => { let _ = msg_sender.send(Command::Faster); },
-                    _ => ()
+                let trimmed = input.trim();
+                if let Some(rest) = trimmed.strip_prefix("l ") {
+                    let mut parts = rest.split_whitespace();
+                    if let Some(path) = parts.next() {
+                        let x = parts.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+                        let y = parts.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+                        let _ = msg_sender.send(Command::Load(path.to_string(), (x, y)));
+                    }
+                } else if let Some(path) = trimmed.strip_prefix("w ") {
+                    let _ = msg_sender.send(Command::Save(path.trim().to_string()));
+                } else if let Some(spec) = trimmed.strip_prefix("b ") {
+                    let _ = msg_sender.send(Command::SetRule(spec.trim().to_string()));
+                } else {
+                    match trimmed {
+                        "q" => break,
+                        "p" => { let _ = msg_sender.send(Command::Pause); },
+                        "r" => { let _ = msg_sender.send(Command::Redraw);},
+                        "m" => { let _ = msg_sender.send(Command::HigherRatio); },
+                        "k" => { let _ = msg_sender.send(Command::LowerRatio); },
+                        "z" => { let _ = msg_sender.send(Command::Slower); },
+                        "x" => { let _ = msg_sender.send(Command::Faster); },
+                        "u" => { let _ = msg_sender.send(Command::StepBack); },
+                        "i" => { let _ = msg_sender.send(Command::Reset); },
+                        "t" => { let _ = msg_sender.send(Command::ToggleTopology); },
+                        _ => ()
+                    }
                 }
             },
             Err(error) => println!("error: {error}")
@@ -83,189 +231,130 @@ fn main() {
     }
 }
 
+fn step_back(history: &mut VecDeque<Board>) -> Result<Board, GameError> {
+    history.pop_back().ok_or(GameError::NoPreviousTurn)
+}
+
 fn generate_board(ratio: f32) -> Board {
     let mut rng = rand::thread_rng();
-    let mut new_board = vec!();
-    for _ in 0..WIDTH * WIDTH {
-        if rng.gen::<f32>() > ratio {
-            new_board.push(Cell::Alive(0));
-        } else {
-            new_board.push(Cell::Dead);
+    let mut board = Board::new();
+    for y in 0..WIDTH as isize {
+        for x in 0..WIDTH as isize {
+            if rng.gen::<f32>() > ratio {
+                board.insert((x, y), 0);
+            }
         }
     }
-
-    new_board.try_into().expect("unable to create board array")
-
+    board
 }
 
-fn draw_board(state: &Board){
+fn draw_board(board: &Board) {
+    let Some((min, max)) = bounding_box(board) else {
+        println!("(empty board)");
+        return;
+    };
+    let (min_x, min_y) = min;
+    let (max_x, max_y) = max;
+    let cols = (max_x - min_x + 1) as usize;
+
     print!(" ");
-    for _ in 0..WIDTH {
+    for _ in 0..cols {
         print!("--");
     }
     println!();
-    for i in 0..WIDTH {
+    for y in min_y..=max_y {
         print!("|");
-        for j in 0..WIDTH {
-            print!("{} ", get_cell_display(&state[(i * WIDTH) + j]));
+        for x in min_x..=max_x {
+            print!("{} ", get_cell_display(board.get(&(x, y)).copied()));
         }
         println!("|");
     }
     print!(" ");
-    for _ in 0..WIDTH {
+    for _ in 0..cols {
         print!("--");
     }
     println!();
 }
 
-fn get_cell_display(cell: &Cell) -> ColoredString {
-    match cell {
-        Cell::Alive(n) => {
-            if *n > 4 {
-                "o".blue()
-            } else if *n > 3 {
-                "o".green()
-            } else if *n > 2 {
-                "o".yellow()
-            } else {
-                "o".white()
-            }
-        },
-        Cell::Dead => " ".white()
-    }
-}
-
-fn get_updated_board(state: &Board) -> Board {
-    let mut new_state = vec!();
-    for i in 0..state.len() {
-        let cell = state[i];
-        let neighbours = get_neighbours(i, state);
-        let count = count_neighbours(neighbours);
-        let new_cell = get_new_cell_state(&cell, count);
-        new_state.push(new_cell);
-    }
-
-    new_state.try_into().expect("unable to create board array")
-}
-
-fn get_new_cell_state(cell: &Cell, neighbour_count: usize) -> Cell {
-    match cell {
-        Cell::Alive(n) => {
-            if neighbour_count > 1 && neighbour_count < 4 {
-                Cell::Alive(n + 1)
-            } else { 
-                Cell:: Dead 
-            }
-        },
-        Cell::Dead => {
-            if neighbour_count == 3 {
-                Cell::Alive(0)
-            } else {
-                Cell::Dead
-            }
-        }
-    }
-}
+fn bounding_box(board: &Board) -> Option<(Cell, Cell)> {
+    let mut cells = board.keys();
+    let &(first_x, first_y) = cells.next()?;
+    let mut min = (first_x, first_y);
+    let mut max = (first_x, first_y);
 
-fn count_neighbours(neighbours: Neighbours) -> usize {
-    let mut count = 0;
-    for cell in neighbours {
-        match cell {
-            Cell::Alive(_) => {
-                count += 1;
-            },
-            _ => ()
-        }
+    for &(x, y) in cells {
+        min = (min.0.min(x), min.1.min(y));
+        max = (max.0.max(x), max.1.max(y));
     }
-    count
-}
-
-fn get_neighbours(cell: usize, state: &Board) -> Neighbours {
-    let neighbours = [
-        get_nw(cell),
-        get_n(cell),
-        get_ne(cell),
-        get_e(cell),
-        get_w(cell),
-        get_sw(cell),
-        get_s(cell),
-        get_se(cell)
-    ];
 
-    neighbours.iter().map(|address|{
-        find_neighbour_state(*address, state)
-    }).collect::<Vec<Cell>>().try_into().expect("unable to create array")
+    Some((min, max))
 }
 
-fn find_neighbour_state(address: Option<usize>, state: &Board) -> Cell {
-    match address {
-        Some(n) => state[n],
-        None => Cell::Dead
+fn get_cell_display(age: Option<u8>) -> ColoredString {
+    match age {
+        Some(n) if n > 4 => "o".blue(),
+        Some(n) if n > 3 => "o".green(),
+        Some(n) if n > 2 => "o".yellow(),
+        Some(_) => "o".white(),
+        None => " ".white()
     }
 }
 
-fn get_nw(cell:usize) -> Option<usize> {
-    if cell % WIDTH == 0 || cell < WIDTH {
-        None
-    } else {
-        Some(cell - (WIDTH + 1))
-    }
+fn normalize_board(board: &Board, topology: Topology) -> Board {
+    board.iter().map(|(&cell, &age)| (topology.wrap(cell), age)).collect()
 }
 
-fn get_n(cell:usize) -> Option<usize> {
-    if cell < WIDTH {
-        None
-    } else {
-        Some(cell - WIDTH)
-    }
-}
-
-fn get_ne(cell: usize) -> Option<usize> {
-    if cell < WIDTH || cell % WIDTH == WIDTH - 1 {
-        None
-    } else {
-        Some(cell - (WIDTH -1 ))
+fn candidate_cells(board: &Board, topology: Topology) -> Vec<Cell> {
+    let mut candidates = HashSet::new();
+    for &cell in board.keys() {
+        candidates.insert(cell);
+        for neighbour in get_neighbours(cell, topology) {
+            candidates.insert(neighbour);
+        }
     }
+    candidates.into_iter().collect()
 }
 
-fn get_w(cell:usize) -> Option<usize> {
-    if cell % WIDTH == 0 {
-        None
-    } else {
-        Some(cell - 1)
-    }
+#[cfg(not(feature = "parallel"))]
+fn get_updated_board(board: &Board, rule: &Rule, topology: Topology) -> Board {
+    let board = normalize_board(board, topology);
+    candidate_cells(&board, topology)
+        .into_iter()
+        .filter_map(|cell| get_new_cell_state(&board, cell, rule, topology).map(|age| (cell, age)))
+        .collect()
 }
 
-fn get_e(cell:usize) -> Option<usize> {
-    if cell % WIDTH == (WIDTH - 1) {
-        None
-    } else {
-        Some(cell + 1)
-    }
+#[cfg(feature = "parallel")]
+fn get_updated_board(board: &Board, rule: &Rule, topology: Topology) -> Board {
+    use rayon::prelude::*;
+
+    let board = normalize_board(board, topology);
+    let candidates = candidate_cells(&board, topology);
+    let workers = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let chunk_size = (candidates.len() / workers).max(1);
+
+    candidates
+        .par_chunks(chunk_size)
+        .flat_map_iter(|chunk| {
+            chunk.iter().filter_map(|&cell| get_new_cell_state(&board, cell, rule, topology).map(|age| (cell, age)))
+        })
+        .collect()
 }
 
-fn get_sw(cell:usize) -> Option<usize> {
-    if cell % WIDTH == 0 || cell >= WIDTH * (WIDTH - 1)  {
-        None
-    } else {
-        Some(cell + (WIDTH - 1))
+fn get_new_cell_state(board: &Board, cell: Cell, rule: &Rule, topology: Topology) -> Option<u8> {
+    let neighbour_count = count_neighbours(board, cell, topology);
+    match board.get(&cell) {
+        Some(age) => rule.survival[neighbour_count].then(|| age.saturating_add(1)),
+        None => rule.birth[neighbour_count].then_some(0)
     }
 }
 
-fn get_s(cell:usize) -> Option<usize> {
-    if cell >= WIDTH * (WIDTH - 1)  {
-        None
-    } else {
-        Some(cell + WIDTH)
-    }
+fn count_neighbours(board: &Board, cell: Cell, topology: Topology) -> usize {
+    get_neighbours(cell, topology).iter().filter(|neighbour| board.contains_key(neighbour)).count()
 }
 
-fn get_se(cell:usize) -> Option<usize> {
-    if cell % WIDTH == WIDTH - 1 || cell >= WIDTH * (WIDTH - 1)  {
-        None
-    } else {
-        Some(cell + WIDTH + 1)
-    }
+fn get_neighbours(cell: Cell, topology: Topology) -> [Cell; 8] {
+    Direction::ALL.map(|direction| direction.neighbour(cell, topology))
 }
 
 
@@ -273,176 +362,179 @@ fn get_se(cell:usize) -> Option<usize> {
 mod tests {
 
     use super::*;
-    use Cell::*;
 
     fn get_board() -> Board {
         [
-            Alive(0), Dead, Alive(0), Alive(0), Dead, Alive(0), Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Alive(0), Dead, Alive(0), Alive(0), Dead, Alive(0), Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Dead, Dead, Dead, Alive(0), Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Dead, Dead, Dead, Dead, Dead, Alive(0), Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Dead, Dead, Dead, Dead, Dead, Alive(0), Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Dead, Dead, Dead, Dead, Dead, Alive(0), Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Alive(0), Dead, Alive(0), Alive(0), Dead, Alive(0), Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Alive(0), Dead, Alive(0), Alive(0), Dead, Alive(0), Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Dead, Dead, Dead, Alive(0), Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Dead, Dead, Dead, Dead, Dead, Alive(0), Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Dead, Dead, Dead, Dead, Dead, Alive(0), Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead,
-            Dead, Dead, Dead, Dead, Dead, Alive(0), Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead
-        ]
+            (0, 0), (2, 0), (3, 0), (5, 0),
+            (0, 1), (2, 1), (3, 1), (5, 1),
+            (3, 2),
+        ].into_iter().map(|cell| (cell, 0)).collect()
     }
 
     #[test]
-    fn test_nw(){
-        assert_eq!(get_nw(0), None);
-        assert_eq!(get_nw(1), None);
-        assert_eq!(get_nw(WIDTH + 1), Some(0));
-        assert_eq!(get_nw(WIDTH + 2), Some(1));
-        assert_eq!(get_nw(WIDTH * 2 + 1), Some(WIDTH));
+    fn direction_all_covers_every_compass_point_once() {
+        let mut offsets: Vec<(isize, isize)> = Direction::ALL.iter().map(|d| d.offset()).collect();
+        offsets.sort();
+        let mut expected: Vec<(isize, isize)> = (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+            .filter(|&offset| offset != (0, 0))
+            .collect();
+        expected.sort();
+        assert_eq!(offsets, expected);
     }
 
     #[test]
-    fn test_n(){
-        assert_eq!(get_n(0), None);
-        assert_eq!(get_n(1), None);
-        assert_eq!(get_n(WIDTH), Some(0));
+    fn test_get_neighbours() {
+        assert_eq!(get_neighbours((0, 0), Topology::Bounded), [
+            (-1, -1), (0, -1), (1, -1),
+            (-1, 0), (1, 0),
+            (-1, 1), (0, 1), (1, 1),
+        ]);
     }
 
     #[test]
-    fn test_ne(){
-        assert_eq!(get_ne(0), None);
-        assert_eq!(get_ne(1), None);
-        assert_eq!(get_ne(WIDTH), Some(1));
-        assert_eq!(get_ne(2 * WIDTH - 1), None);
+    fn toroidal_neighbours_wrap_around_the_board() {
+        let last = (WIDTH - 1) as isize;
+        assert_eq!(get_neighbours((0, 0), Topology::Toroidal), [
+            (last, last), (0, last), (1, last),
+            (last, 0), (1, 0),
+            (last, 1), (0, 1), (1, 1),
+        ]);
     }
 
     #[test]
-    fn test_w(){
-        assert_eq!(get_w(0), None);
-        assert_eq!(get_w(1), Some(0));
-        assert_eq!(get_w(WIDTH), None);
-        assert_eq!(get_w(WIDTH - 1), Some(WIDTH - 2));
+    fn count_neighbours_returns_alive_count() {
+        let board = get_board();
+        assert_eq!(count_neighbours(&board, (1, 0), Topology::Bounded), 4);
+        assert_eq!(count_neighbours(&board, (10, 10), Topology::Bounded), 0);
+        assert_eq!(count_neighbours(&board, (3, 1), Topology::Bounded), 4);
     }
 
     #[test]
-    fn test_e(){
-        assert_eq!(get_e(0), Some(1));
-        assert_eq!(get_e(1), Some(2));
-        assert_eq!(get_e(WIDTH - 1), None);
-        assert_eq!(get_e(WIDTH), Some(WIDTH + 1));
-        assert_eq!(get_e(WIDTH * WIDTH - 1), None);
+    fn when_alive_should_die_for_less_than_2_neighbours() {
+        let board: Board = [((0, 0), 0)].into_iter().collect();
+        assert_eq!(get_new_cell_state(&board, (0, 0), &Rule::default(), Topology::Bounded), None);
     }
 
     #[test]
-    fn test_sw(){
-        assert_eq!(get_sw(0), None);
-        assert_eq!(get_sw(1), Some(WIDTH));
-        assert_eq!(get_sw(WIDTH + 1), Some(2 * WIDTH));
-        assert_eq!(get_sw(WIDTH + 2), Some(2 * WIDTH + 1));
-        assert_eq!(get_sw(WIDTH * (WIDTH - 1) + 1), None);
-        
+    fn when_alive_should_die_for_more_than_3_neighbours() {
+        let board: Board = [(0, 0), (1, 0), (-1, 0), (0, 1), (0, -1)].into_iter().map(|c| (c, 0)).collect();
+        assert_eq!(get_new_cell_state(&board, (0, 0), &Rule::default(), Topology::Bounded), None);
     }
 
     #[test]
-    fn test_s(){
-        assert_eq!(get_s(0), Some(WIDTH));
-        assert_eq!(get_s(1), Some(WIDTH + 1));
-        assert_eq!(get_s(WIDTH * (WIDTH - 1) + 1), None);
-        assert_eq!(get_s(WIDTH * (WIDTH - 1)), None)
-        
+    fn when_alive_should_survive_at_2_or_3_neighbours() {
+        let board: Board = [(0, 0), (1, 0), (-1, 0)].into_iter().map(|c| (c, 0)).collect();
+        assert_eq!(get_new_cell_state(&board, (0, 0), &Rule::default(), Topology::Bounded), Some(1));
     }
 
-    
     #[test]
-    fn test_se(){
-        assert_eq!(get_se(0), Some(WIDTH + 1));
-        assert_eq!(get_se(1), Some(WIDTH + 2));
-        assert_eq!(get_se(WIDTH * (WIDTH - 1) + 1), None);
-        assert_eq!(get_se((WIDTH * WIDTH) - 1), None);
-        
+    fn when_a_cell_survives_its_age_increments() {
+        let board: Board = [((0, 0), 4), ((1, 0), 0), ((-1, 0), 0)].into_iter().collect();
+        assert_eq!(get_new_cell_state(&board, (0, 0), &Rule::default(), Topology::Bounded), Some(5));
     }
 
     #[test]
-    fn test_neighbour_state_when_none() {
-        let board = get_board();
-        assert_eq!(find_neighbour_state(None, &board), Dead);
+    fn when_dead_should_remain_dead_when_not_3_neighbours() {
+        let board: Board = [(1, 0), (-1, 0)].into_iter().map(|c| (c, 0)).collect();
+        assert_eq!(get_new_cell_state(&board, (0, 0), &Rule::default(), Topology::Bounded), None);
     }
 
     #[test]
-    fn test_neighbour_state_when_alive() {
-        let board = get_board();
-        assert_eq!(find_neighbour_state(Some(0), &board), Alive(0));
+    fn when_dead_should_become_alive_for_3_neighbours() {
+        let board: Board = [(1, 0), (-1, 0), (0, 1)].into_iter().map(|c| (c, 0)).collect();
+        assert_eq!(get_new_cell_state(&board, (0, 0), &Rule::default(), Topology::Bounded), Some(0));
     }
 
     #[test]
-    fn test_neighbour_state_when_dead() {
-        let board = get_board();
-        assert_eq!(find_neighbour_state(Some(1), &board), Dead);
+    fn highlife_births_on_six_neighbours_unlike_conway() {
+        let highlife = Rule::parse("B36/S23").unwrap();
+        let board: Board = [(-1, -1), (1, -1), (-1, 1), (1, 1), (-1, 0), (1, 0)]
+            .into_iter().map(|c| (c, 0)).collect();
+        assert_eq!(get_new_cell_state(&board, (0, 0), &Rule::default(), Topology::Bounded), None);
+        assert_eq!(get_new_cell_state(&board, (0, 0), &highlife, Topology::Bounded), Some(0));
     }
 
     #[test]
-    fn test_get_neighbours(){
-        let board = get_board();
-        assert_eq!(get_neighbours(0, &board), [Dead, Dead, Dead, Dead, Dead, Dead, Alive(0), Dead]);
-        assert_eq!(get_neighbours(1, &board), [Dead, Dead, Dead, Alive(0), Alive(0), Alive(0), Dead, Alive(0)]);
+    fn glider_moves_beyond_a_fixed_width_boundary() {
+        let mut board: Board = [
+            (1, 0),
+            (2, 1),
+            (0, 2), (1, 2), (2, 2),
+        ].into_iter().map(|c| (c, 0)).collect();
+
+        let rule = Rule::default();
+        for _ in 0..(WIDTH * 4) {
+            board = get_updated_board(&board, &rule, Topology::Bounded);
+        }
+
+        assert!(!board.is_empty());
+        let (_, max) = bounding_box(&board).unwrap();
+        assert!(max.0 as usize > WIDTH || max.1 as usize > WIDTH);
     }
 
     #[test]
-    fn count_neighbours_returns_alive_count(){
-        let neighbours = [Dead, Dead, Alive(0), Dead, Dead, Dead, Dead, Dead];
-        assert_eq!(count_neighbours(neighbours), 1);
-
-        let neighbours = [Alive(0), Alive(0), Alive(0), Alive(0), Alive(0), Alive(0), Alive(0), Alive(0)];
-        assert_eq!(count_neighbours(neighbours), 8);
+    fn glider_stays_within_the_board_when_toroidal() {
+        let mut board: Board = [
+            (1, 0),
+            (2, 1),
+            (0, 2), (1, 2), (2, 2),
+        ].into_iter().map(|c| (c, 0)).collect();
+
+        let rule = Rule::default();
+        for _ in 0..(WIDTH * 4) {
+            board = get_updated_board(&board, &rule, Topology::Toroidal);
+        }
 
-        let neighbours = [Dead, Dead, Dead, Dead, Dead, Dead, Dead, Dead];
-        assert_eq!(count_neighbours(neighbours), 0);
+        assert!(!board.is_empty());
+        let (min, max) = bounding_box(&board).unwrap();
+        assert!(min.0 >= 0 && min.1 >= 0);
+        assert!((max.0 as usize) < WIDTH && (max.1 as usize) < WIDTH);
     }
 
     #[test]
-    fn when_alive_should_die_for_less_than_2_neighbours(){
-        assert_eq!(get_new_cell_state(&Cell::Alive(0), 1), Cell::Dead);
-        assert_eq!(get_new_cell_state(&Cell::Alive(0), 0), Cell::Dead);
+    fn normalize_board_wraps_a_stray_out_of_range_cell_under_toroidal() {
+        let board: Board = [(((WIDTH + 5) as isize, 3), 0)].into_iter().collect();
+        let normalized = normalize_board(&board, Topology::Toroidal);
+        assert!(!normalized.contains_key(&((WIDTH + 5) as isize, 3)));
+        assert!(normalized.contains_key(&(5, 3)));
     }
 
-    
     #[test]
-    fn when_alive_should_die_for_more_than_3_neighbours(){
-        assert_eq!(get_new_cell_state(&Cell::Alive(0), 4), Cell::Dead);
-        assert_eq!(get_new_cell_state(&Cell::Alive(0), 5), Cell::Dead);
+    fn get_updated_board_folds_a_stray_cell_onto_the_torus() {
+        let stray = (WIDTH + 5) as isize;
+        let board: Board = [(stray, 3), (stray + 1, 3), (stray, 4), (stray + 1, 4)]
+            .into_iter().map(|c| (c, 0)).collect();
+        let next = get_updated_board(&board, &Rule::default(), Topology::Toroidal);
+        assert!(!next.contains_key(&(stray, 3)));
+        assert!(next.contains_key(&(5, 3)));
     }
 
     #[test]
-    fn when_alive_should_survive_at_2_or_3_neighbours(){
-        assert_eq!(get_new_cell_state(&Cell::Alive(0), 2), Cell::Alive(1));
-        assert_eq!(get_new_cell_state(&Cell::Alive(0), 3), Cell::Alive(1));
+    fn toggle_switches_between_topologies() {
+        assert_eq!(Topology::Bounded.toggle(), Topology::Toroidal);
+        assert_eq!(Topology::Toroidal.toggle(), Topology::Bounded);
     }
 
     #[test]
-    fn when_a_cell_survives_its_age_increments(){
-        assert_eq!(get_new_cell_state(&Cell::Alive(0), 2), Cell::Alive(1));
-        assert_eq!(get_new_cell_state(&Cell::Alive(1), 3), Cell::Alive(2));
+    fn bounding_box_is_none_for_an_empty_board() {
+        assert_eq!(bounding_box(&Board::new()), None);
     }
 
     #[test]
-    fn when_dead_should_remain_dead_when_not_3_neighbours(){
-        assert_eq!(get_new_cell_state(&Cell::Dead, 0), Cell::Dead);
-        assert_eq!(get_new_cell_state(&Cell::Dead, 1), Cell::Dead);
-        assert_eq!(get_new_cell_state(&Cell::Dead, 2), Cell::Dead);
-        assert_eq!(get_new_cell_state(&Cell::Dead, 4), Cell::Dead);
-        assert_eq!(get_new_cell_state(&Cell::Dead, 5), Cell::Dead);
+    fn step_back_pops_the_most_recent_history_entry() {
+        let mut history = VecDeque::new();
+        history.push_back(get_board());
+        let earlier: Board = HashMap::new();
+        history.push_back(earlier.clone());
+
+        assert_eq!(step_back(&mut history), Ok(earlier));
+        assert_eq!(step_back(&mut history), Ok(get_board()));
     }
 
     #[test]
-    fn when_dead_should_alive_for_3_neighbours(){
-        assert_eq!(get_new_cell_state(&Cell::Dead, 3), Cell::Alive(0));
+    fn step_back_errors_when_history_is_empty() {
+        let mut history: VecDeque<Board> = VecDeque::new();
+        assert_eq!(step_back(&mut history), Err(GameError::NoPreviousTurn));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,211 @@
+use std::collections::HashSet;
+use std::fmt;
+
+type Cell = (isize, isize);
+
+#[derive(Debug)]
+pub enum PatternError {
+    Io(std::io::Error),
+    MissingHeader,
+    InvalidRunCount,
+    UnknownTag(char),
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternError::Io(e) => write!(f, "{e}"),
+            PatternError::MissingHeader => write!(f, "RLE pattern is missing its 'x = m, y = n' header"),
+            PatternError::InvalidRunCount => write!(f, "RLE pattern has a malformed run-length count"),
+            PatternError::UnknownTag(c) => write!(f, "RLE pattern has an unknown tag '{c}'"),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+impl From<std::io::Error> for PatternError {
+    fn from(e: std::io::Error) -> Self {
+        PatternError::Io(e)
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<Cell>, PatternError> {
+    let looks_like_rle = input
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('!'))
+        .map(|line| line.starts_with("x ="))
+        .unwrap_or(false);
+
+    if looks_like_rle {
+        parse_rle(input)
+    } else {
+        Ok(parse_plaintext(input))
+    }
+}
+
+fn parse_plaintext(input: &str) -> Vec<Cell> {
+    let mut cells = Vec::new();
+    let mut y: isize = 0;
+    for line in input.lines() {
+        if line.starts_with('!') {
+            continue;
+        }
+        for (x, ch) in line.chars().enumerate() {
+            if ch == 'O' || ch == 'X' {
+                cells.push((x as isize, y));
+            }
+        }
+        y += 1;
+    }
+    cells
+}
+
+fn parse_rle(input: &str) -> Result<Vec<Cell>, PatternError> {
+    let mut saw_header = false;
+    let mut body = String::new();
+
+    for line in input.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !saw_header {
+            if line.starts_with("x =") {
+                saw_header = true;
+                continue;
+            }
+            return Err(PatternError::MissingHeader);
+        }
+        body.push_str(line);
+    }
+
+    if !saw_header {
+        return Err(PatternError::MissingHeader);
+    }
+
+    let mut cells = Vec::new();
+    let mut x: isize = 0;
+    let mut y: isize = 0;
+    let mut count_buf = String::new();
+
+    for ch in body.chars() {
+        if ch.is_ascii_digit() {
+            count_buf.push(ch);
+            continue;
+        }
+
+        let count: isize = if count_buf.is_empty() {
+            1
+        } else {
+            count_buf.parse().map_err(|_| PatternError::InvalidRunCount)?
+        };
+        count_buf.clear();
+
+        match ch {
+            'b' => x += count,
+            'o' => {
+                for i in 0..count {
+                    cells.push((x + i, y));
+                }
+                x += count;
+            }
+            '$' => {
+                y += count;
+                x = 0;
+            }
+            '!' => break,
+            other => return Err(PatternError::UnknownTag(other)),
+        }
+    }
+
+    Ok(cells)
+}
+
+pub fn to_rle(cells: &HashSet<Cell>) -> String {
+    if cells.is_empty() {
+        return "x = 0, y = 0\n!\n".to_string();
+    }
+
+    let min_x = cells.iter().map(|c| c.0).min().unwrap();
+    let max_x = cells.iter().map(|c| c.0).max().unwrap();
+    let min_y = cells.iter().map(|c| c.1).min().unwrap();
+    let max_y = cells.iter().map(|c| c.1).max().unwrap();
+
+    let mut out = format!("x = {}, y = {}\n", max_x - min_x + 1, max_y - min_y + 1);
+    for y in min_y..=max_y {
+        out.push_str(&encode_row(cells, y, min_x, max_x));
+        if y != max_y {
+            out.push('$');
+        }
+    }
+    out.push('!');
+    out.push('\n');
+    out
+}
+
+fn encode_row(cells: &HashSet<Cell>, y: isize, min_x: isize, max_x: isize) -> String {
+    let mut runs: Vec<(char, usize)> = Vec::new();
+    for x in min_x..=max_x {
+        let tag = if cells.contains(&(x, y)) { 'o' } else { 'b' };
+        match runs.last_mut() {
+            Some((last_tag, count)) if *last_tag == tag => *count += 1,
+            _ => runs.push((tag, 1)),
+        }
+    }
+
+    if matches!(runs.last(), Some((tag, _)) if *tag == 'b') {
+        runs.pop();
+    }
+
+    runs.into_iter()
+        .map(|(tag, count)| if count > 1 { format!("{count}{tag}") } else { tag.to_string() })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_plaintext_with_comments() {
+        let input = "!Name: blinker\n.O.\n.O.\n.O.\n";
+        let mut cells = parse(input).unwrap();
+        cells.sort();
+        assert_eq!(cells, vec![(1, 0), (1, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn parses_rle_blinker() {
+        let input = "#N blinker\nx = 3, y = 1\n3o!\n";
+        let mut cells = parse(input).unwrap();
+        cells.sort();
+        assert_eq!(cells, vec![(0, 0), (1, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn parses_rle_glider() {
+        let input = "x = 3, y = 3\nbob$2bo$3o!\n";
+        let mut cells = parse(input).unwrap();
+        cells.sort();
+        assert_eq!(cells, vec![(0, 2), (1, 0), (1, 2), (2, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn rle_roundtrips_through_parse() {
+        let original: HashSet<Cell> = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)].into_iter().collect();
+        let rle = to_rle(&original);
+        let mut parsed = parse(&rle).unwrap();
+        parsed.sort();
+        let mut expected: Vec<Cell> = original.into_iter().collect();
+        expected.sort();
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn rejects_unknown_rle_tag() {
+        let input = "x = 1, y = 1\n1z!\n";
+        assert!(matches!(parse(input), Err(PatternError::UnknownTag('z'))));
+    }
+}
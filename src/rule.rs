@@ -0,0 +1,95 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    pub birth: [bool; 9],
+    pub survival: [bool; 9],
+}
+
+impl Rule {
+    pub const CONWAY: &'static str = "B3/S23";
+
+    pub fn parse(spec: &str) -> Result<Rule, RuleParseError> {
+        let spec = spec.trim();
+        let (birth_part, survival_part) = spec
+            .split_once('/')
+            .ok_or(RuleParseError::Malformed)?;
+
+        Ok(Rule {
+            birth: parse_counts(birth_part, 'B')?,
+            survival: parse_counts(survival_part, 'S')?,
+        })
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::parse(Rule::CONWAY).expect("Conway's rule is always valid")
+    }
+}
+
+fn parse_counts(part: &str, tag: char) -> Result<[bool; 9], RuleParseError> {
+    let digits = part.strip_prefix(tag).ok_or(RuleParseError::Malformed)?;
+    let mut table = [false; 9];
+    for ch in digits.chars() {
+        let n = ch.to_digit(10).ok_or(RuleParseError::Malformed)? as usize;
+        if n > 8 {
+            return Err(RuleParseError::OutOfRange(n));
+        }
+        table[n] = true;
+    }
+    Ok(table)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RuleParseError {
+    Malformed,
+    OutOfRange(usize),
+}
+
+impl fmt::Display for RuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleParseError::Malformed => write!(f, "rule must look like 'B3/S23'"),
+            RuleParseError::OutOfRange(n) => write!(f, "neighbour count {n} is not between 0 and 8"),
+        }
+    }
+}
+
+impl std::error::Error for RuleParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_conway() {
+        let rule = Rule::parse("B3/S23").unwrap();
+        assert_eq!(rule.birth, [false, false, false, true, false, false, false, false, false]);
+        assert_eq!(rule.survival, [false, false, true, true, false, false, false, false, false]);
+    }
+
+    #[test]
+    fn parses_highlife() {
+        let rule = Rule::parse("B36/S23").unwrap();
+        assert!(rule.birth[3] && rule.birth[6]);
+        assert!(!rule.birth[2]);
+    }
+
+    #[test]
+    fn parses_seeds_with_empty_survival() {
+        let rule = Rule::parse("B2/S").unwrap();
+        assert!(rule.birth[2]);
+        assert_eq!(rule.survival, [false; 9]);
+    }
+
+    #[test]
+    fn rejects_missing_slash() {
+        assert_eq!(Rule::parse("B3S23"), Err(RuleParseError::Malformed));
+    }
+
+    #[test]
+    fn rejects_out_of_range_digit() {
+        assert_eq!(Rule::parse("B9/S23"), Err(RuleParseError::OutOfRange(9)));
+    }
+}